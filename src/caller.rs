@@ -0,0 +1,119 @@
+//! Decodes the host-provided `CallingAppInfo` and matches it against an entry's allowlist, so a
+//! credential is only disclosed to a caller its entry opts in to.
+
+use crate::host::CallingAppInfo;
+use crate::model::Entry;
+
+/// The decoded identity of whichever WASM caller is making this request.
+pub struct CallingApp {
+    pub package: String,
+    pub origin: String,
+}
+
+impl CallingApp {
+    /// Decodes `info`'s fixed-size, NUL-padded byte arrays into owned strings, trimming at the
+    /// first NUL. Invalid UTF-8 decodes to an empty string rather than failing the whole call.
+    pub fn decode(info: &CallingAppInfo) -> CallingApp {
+        CallingApp {
+            package: decode_fixed(&info.package_name),
+            origin: decode_fixed(&info.origin),
+        }
+    }
+
+    /// Whether `entry` allows this caller, per its `allowed_packages`/`allowed_origins` lists.
+    /// An entry with no allowlist in a dimension allows any caller for that dimension; a `"*"`
+    /// entry in either list is an explicit wildcard escape hatch.
+    pub fn is_allowed(&self, entry: &Entry) -> bool {
+        Self::matches(&entry.allowed_packages, &self.package)
+            && Self::matches(&entry.allowed_origins, &self.origin)
+    }
+
+    fn matches(allowlist: &Option<Vec<String>>, value: &str) -> bool {
+        match allowlist {
+            None => true,
+            Some(allowed) => allowed.iter().any(|allowed| allowed == "*" || allowed == value),
+        }
+    }
+}
+
+fn decode_fixed(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..end]).unwrap_or("").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DisplayInfo;
+
+    fn entry_with_allowlist(
+        allowed_packages: Option<Vec<String>>,
+        allowed_origins: Option<Vec<String>>,
+    ) -> Entry {
+        Entry {
+            id: "entry-1".to_string(),
+            supported_credential_types: None,
+            display_info: DisplayInfo {
+                user_name: "alice".to_string(),
+                icon_id: None,
+                account_name: None,
+                provider_name: None,
+                disclaimer: None,
+                warning: None,
+            },
+            transfer: None,
+            allowed_packages,
+            allowed_origins,
+        }
+    }
+
+    fn caller(package: &str, origin: &str) -> CallingApp {
+        CallingApp { package: package.to_string(), origin: origin.to_string() }
+    }
+
+    #[test]
+    fn no_allowlist_allows_any_caller() {
+        let entry = entry_with_allowlist(None, None);
+        assert!(caller("com.example.app", "https://example.com").is_allowed(&entry));
+    }
+
+    #[test]
+    fn matching_package_and_origin_is_allowed() {
+        let entry = entry_with_allowlist(
+            Some(vec!["com.example.app".to_string()]),
+            Some(vec!["https://example.com".to_string()]),
+        );
+        assert!(caller("com.example.app", "https://example.com").is_allowed(&entry));
+    }
+
+    #[test]
+    fn non_matching_package_is_rejected() {
+        let entry = entry_with_allowlist(Some(vec!["com.example.app".to_string()]), None);
+        assert!(!caller("com.evil.app", "https://example.com").is_allowed(&entry));
+    }
+
+    #[test]
+    fn non_matching_origin_is_rejected() {
+        let entry = entry_with_allowlist(None, Some(vec!["https://example.com".to_string()]));
+        assert!(!caller("com.example.app", "https://evil.example").is_allowed(&entry));
+    }
+
+    #[test]
+    fn wildcard_entry_allows_any_caller() {
+        let entry = entry_with_allowlist(Some(vec!["*".to_string()]), Some(vec!["*".to_string()]));
+        assert!(caller("anything", "anything").is_allowed(&entry));
+    }
+
+    #[test]
+    fn decode_fixed_trims_at_first_nul() {
+        let mut bytes = [0u8; 16];
+        bytes[..5].copy_from_slice(b"hello");
+        assert_eq!(decode_fixed(&bytes), "hello");
+    }
+
+    #[test]
+    fn decode_fixed_rejects_invalid_utf8() {
+        let bytes = [0xFF, 0xFE, 0x00, 0x00];
+        assert_eq!(decode_fixed(&bytes), "");
+    }
+}
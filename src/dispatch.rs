@@ -0,0 +1,173 @@
+//! Renders a matched `Entry` through the host call appropriate to its `CredentialKind`, instead
+//! of funneling every match into `AddExportEntry` with a hardcoded provider name.
+
+use std::ffi::CString;
+
+use crate::host;
+use crate::model::{CredentialKind, Entry, TransferMode};
+
+/// Resolved icon bytes for an entry, or `(null, 0)` when it has none.
+pub struct Icon {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl Icon {
+    pub fn none() -> Icon {
+        Icon { ptr: std::ptr::null(), len: 0 }
+    }
+}
+
+/// Dispatches a matched entry to the host call that matches its `CredentialKind`, inlining the
+/// full field set in `ByValue` mode and only a compact identifier in `ByReference` mode.
+///
+/// # Safety
+/// Calls directly into the `credman` host imports; `icon` must point at `icon.len` valid bytes
+/// (or be null with `len == 0`).
+pub unsafe fn dispatch_entry(entry: &Entry, kind: CredentialKind, icon: Icon, mode: TransferMode) {
+    let by_value = mode == TransferMode::ByValue;
+    match kind {
+        CredentialKind::Password | CredentialKind::Passkey => {
+            add_string_id_entry(entry, icon, by_value, None)
+        }
+        CredentialKind::Export => add_export_entry(entry, icon, by_value),
+        CredentialKind::Custom(credential_type) => match entry.id.parse::<i64>() {
+            Ok(numeric_id) => add_entry(numeric_id, entry, icon, by_value),
+            Err(_) => add_string_id_entry(entry, icon, by_value, Some(&credential_type)),
+        },
+    }
+}
+
+/// In `ByValue` mode this also populates the per-attribute `AddField` calls (plus a "credential
+/// type" field when `credential_type` is set, for kinds not captured by the host's fixed
+/// `Password`/`Passkey`/`Export` vocabulary); in `ByReference` mode it emits only the id and a
+/// minimal title so the host can fetch the rest later.
+unsafe fn add_string_id_entry(entry: &Entry, icon: Icon, by_value: bool, credential_type: Option<&str>) {
+    // `entry.id`/`user_name` come from the credential store, not a literal, so a NUL byte in
+    // either must not panic the module; fall back to an empty `CString` like the other fields.
+    let cred_id = CString::new(entry.id.as_str()).unwrap_or_default();
+    let title = CString::new(entry.display_info.user_name.as_str()).unwrap_or_default();
+    let subtitle = if by_value {
+        CString::new(entry.display_info.account_name.clone().unwrap_or_default())
+            .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let disclaimer = if by_value {
+        CString::new(entry.display_info.disclaimer.clone().unwrap_or_default())
+            .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let warning = if by_value {
+        CString::new(entry.display_info.warning.clone().unwrap_or_default()).unwrap_or_default()
+    } else {
+        CString::default()
+    };
+
+    unsafe {
+        host::AddStringIdEntry(
+            cred_id.as_ptr(),
+            icon.ptr,
+            icon.len,
+            title.as_ptr(),
+            subtitle.as_ptr(),
+            disclaimer.as_ptr(),
+            warning.as_ptr(),
+        );
+    }
+
+    if by_value {
+        add_field("username", &entry.display_info.user_name);
+        if let Some(ref account_name) = entry.display_info.account_name {
+            add_field("relying party", account_name);
+        }
+        if let Some(credential_type) = credential_type {
+            add_field("credential type", credential_type);
+        }
+    }
+}
+
+unsafe fn add_field(display_name: &str, display_value: &str) {
+    // `cred_id` is unused by the host for `AddField`; it always attaches to whichever entry was
+    // most recently added, string-id or not, so there's no numeric id to pass through here.
+    let display_name = CString::new(display_name).unwrap();
+    let display_value = CString::new(display_value).unwrap_or_default();
+    unsafe {
+        host::AddField(0, display_name.as_ptr(), display_value.as_ptr());
+    }
+}
+
+unsafe fn add_entry(cred_id: i64, entry: &Entry, icon: Icon, by_value: bool) {
+    let title = CString::new(entry.display_info.user_name.as_str()).unwrap_or_default();
+    let subtitle = if by_value {
+        CString::new(entry.display_info.account_name.clone().unwrap_or_default())
+            .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let disclaimer = if by_value {
+        CString::new(entry.display_info.disclaimer.clone().unwrap_or_default())
+            .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let warning = if by_value {
+        CString::new(entry.display_info.warning.clone().unwrap_or_default()).unwrap_or_default()
+    } else {
+        CString::default()
+    };
+
+    unsafe {
+        host::AddEntry(
+            cred_id,
+            icon.ptr,
+            icon.len,
+            title.as_ptr(),
+            subtitle.as_ptr(),
+            disclaimer.as_ptr(),
+            warning.as_ptr(),
+        );
+    }
+}
+
+/// In `ByValue` mode this discloses the username/provider/display name alongside the id; in
+/// `ByReference` mode it emits only the id so the host can fetch the rest later, same as
+/// `add_string_id_entry`.
+unsafe fn add_export_entry(entry: &Entry, icon: Icon, by_value: bool) {
+    let id = CString::new(entry.id.as_str()).unwrap_or_default();
+    let username = if by_value {
+        CString::new(entry.display_info.user_name.as_str()).unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let provider = if by_value {
+        CString::new(
+            entry
+                .display_info
+                .provider_name
+                .clone()
+                .unwrap_or_else(|| "default_provider".to_string()),
+        )
+        .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+    let display_name = if by_value {
+        CString::new(entry.display_info.account_name.clone().unwrap_or_default())
+            .unwrap_or_default()
+    } else {
+        CString::default()
+    };
+
+    unsafe {
+        host::AddExportEntry(
+            id.as_ptr(),
+            icon.ptr,
+            icon.len,
+            username.as_ptr(),
+            provider.as_ptr(),
+            display_name.as_ptr(),
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Raw WASM imports from the `credman` host module, plus the structs laid out to match them.
+
+use std::ffi::c_void;
+use std::os::raw::c_char;
+
+#[link(wasm_import_module = "credman")]
+extern "C" {
+    pub fn AddEntry(
+        cred_id: i64,
+        icon: *const u8,
+        icon_len: usize,
+        title: *const c_char,
+        subtitle: *const c_char,
+        disclaimer: *const c_char,
+        warning: *const c_char,
+    );
+    pub fn AddField(cred_id: i64, field_display_name: *const c_char, field_display_value: *const c_char);
+    pub fn AddStringIdEntry(
+        cred_id: *const c_char,
+        icon: *const u8,
+        icon_len: usize,
+        title: *const c_char,
+        subtitle: *const c_char,
+        disclaimer: *const c_char,
+        warning: *const c_char,
+    );
+
+    // Note: The C code used import_name("AddExportEntry"), mapping here.
+    pub fn AddExportEntry(
+        cred_id: *const c_char,
+        icon: *const u8,
+        icon_len: usize,
+        username: *const c_char,
+        provider_name: *const c_char,
+        display_name: *const c_char,
+    );
+
+    pub fn GetRequestBuffer(buffer: *mut c_void);
+    pub fn GetRequestSize(size: *mut u32);
+    pub fn ReadCredentialsBuffer(buffer: *mut c_void, offset: usize, len: usize) -> usize;
+    pub fn GetCredentialsSize(size: *mut u32);
+    pub fn GetCallingAppInfo(info: *mut CallingAppInfo);
+}
+
+#[repr(C)]
+pub struct CallingAppInfo {
+    pub package_name: [u8; 256],
+    pub origin: [u8; 512],
+}
@@ -0,0 +1,44 @@
+//! Single choke point for deserializing the request/credentials JSON blobs.
+//!
+//! Both buffers are owned `Vec<u8>`s we already control, so with the `simd` feature enabled we
+//! parse them in place with `simd_json`, which tokenizes with vectorized instructions and
+//! deserializes straight into the caller's `Deserialize` type. Without the feature (or on a
+//! target without AVX2) we fall back to `serde_json`, which still needs a validated `&str`.
+//! Either way the null-terminator trimming that used to live inline at the credentials call
+//! site happens exactly once, here.
+
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidUtf8,
+    Json,
+}
+
+/// Trims trailing NUL bytes (the credentials blob may be NUL-padded) and deserializes `bytes`
+/// into `T`, preferring the SIMD-accelerated path when the `simd` feature is enabled.
+pub fn parse_json<T: DeserializeOwned>(bytes: &mut [u8]) -> Result<T, ParseError> {
+    let len = bytes
+        .iter()
+        .rposition(|&b| b != 0)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let bytes = &mut bytes[..len];
+
+    #[cfg(all(feature = "simd", target_feature = "avx2"))]
+    {
+        simd_json::serde::from_slice(bytes).map_err(|err| {
+            if matches!(err.error(), simd_json::ErrorType::InvalidUtf8) {
+                ParseError::InvalidUtf8
+            } else {
+                ParseError::Json
+            }
+        })
+    }
+
+    #[cfg(not(all(feature = "simd", target_feature = "avx2")))]
+    {
+        let s = std::str::from_utf8(bytes).map_err(|_| ParseError::InvalidUtf8)?;
+        serde_json::from_str(s).map_err(|_| ParseError::Json)
+    }
+}
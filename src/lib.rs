@@ -1,69 +1,27 @@
-use std::ffi::{c_void, CString};
-use std::os::raw::{c_char, c_int};
-use std::slice;
-use serde::Deserialize;
+// `main` below (the wasm module's only entry point) is compiled out under `#[cfg(test)]` to avoid
+// clashing with the test harness's own `main`; that leaves everything it transitively calls
+// looking unreachable to the test build's dead-code pass even though it's very much reachable in
+// a real build.
+#![cfg_attr(test, allow(dead_code))]
+
+use std::ffi::c_void;
+
+mod caller;
+mod dispatch;
+mod host;
+mod json;
+mod model;
+mod parser;
+mod status;
+
+use caller::CallingApp;
+use dispatch::Icon;
+use host::CallingAppInfo;
+use model::{Credentials, Request};
+use status::Status;
 
 // -------------------------------------------------------------------------
-// 1. WASM Imports (Mapping to credman module)
-// -------------------------------------------------------------------------
-
-#[link(wasm_import_module = "credman")]
-extern "C" {
-    fn AddEntry(cred_id: i64, icon: *const u8, icon_len: usize, title: *const c_char, subtitle: *const c_char, disclaimer: *const c_char, warning: *const c_char);
-    fn AddField(cred_id: i64, field_display_name: *const c_char, field_display_value: *const c_char);
-    fn AddStringIdEntry(cred_id: *const c_char, icon: *const u8, icon_len: usize, title: *const c_char, subtitle: *const c_char, disclaimer: *const c_char, warning: *const c_char);
-    
-    // Note: The C code used import_name("AddExportEntry"), mapping here.
-    fn AddExportEntry(cred_id: *const c_char, icon: *const u8, icon_len: usize, username: *const c_char, provider_name: *const c_char, display_name: *const c_char);
-
-    fn GetRequestBuffer(buffer: *mut c_void);
-    fn GetRequestSize(size: *mut u32);
-    fn ReadCredentialsBuffer(buffer: *mut c_void, offset: usize, len: usize) -> usize;
-    fn GetCredentialsSize(size: *mut u32);
-    fn GetCallingAppInfo(info: *mut CallingAppInfo);
-}
-
-// -------------------------------------------------------------------------
-// 2. Struct Definitions (JSON & Binary mapping)
-// -------------------------------------------------------------------------
-
-#[repr(C)]
-struct CallingAppInfo {
-    package_name: [u8; 256],
-    origin: [u8; 512],
-}
-
-// JSON: Request Schema
-#[derive(Deserialize)]
-struct Request {
-    #[serde(rename = "credentialTypes")]
-    credential_types: Option<Vec<String>>,
-}
-
-// JSON: Display Info inside an Entry
-#[derive(Deserialize)]
-struct DisplayInfo {
-    user_name: String,
-    icon_id: Option<usize>,
-    account_name: Option<String>,
-}
-
-// JSON: Entry Schema
-#[derive(Deserialize)]
-struct Entry {
-    id: String,
-    supported_credential_types: Option<Vec<String>>,
-    display_info: DisplayInfo,
-}
-
-// JSON: Credentials Root Schema
-#[derive(Deserialize)]
-struct Credentials {
-    entries: Option<Vec<Entry>>,
-}
-
-// -------------------------------------------------------------------------
-// 3. Helper Functions
+// Helper Functions
 // -------------------------------------------------------------------------
 
 /// Allocates memory and fetches data from the host
@@ -75,7 +33,7 @@ fn get_buffer_from_host(
         let mut size: u32 = 0;
         size_fn(&mut size);
         if size == 0 { return Vec::new(); }
-        
+
         let mut buffer = Vec::with_capacity(size as usize);
         data_fn(buffer.as_mut_ptr() as *mut c_void);
         buffer.set_len(size as usize);
@@ -83,14 +41,8 @@ fn get_buffer_from_host(
     }
 }
 
-/// Helper to parse an integer from a byte slice (Little Endian as per WASM standard)
-fn read_i32(buffer: &[u8], offset: usize) -> i32 {
-    let bytes = &buffer[offset..offset+4];
-    i32::from_ne_bytes(bytes.try_into().unwrap())
-}
-
 // -------------------------------------------------------------------------
-// 4. Main Logic
+// Main Logic
 // -------------------------------------------------------------------------
 
 // Credman expects this as the entry point, but it isn't there if the target is wasm32-unknown-unknown.
@@ -100,128 +52,84 @@ extern "C" fn _start() {
     main();
 }
 
+// The `cargo test` harness binary supplies its own `main`; an unconditional `#[no_mangle]` here
+// would collide with it at link time, so this exported entry point only exists in non-test builds.
+#[cfg(not(test))]
 #[no_mangle]
 pub extern "C" fn main() -> i32 {
+    match run() {
+        Ok(_exported_count) => Status::Ok.code(),
+        Err(status) => status.code(),
+    }
+}
+
+/// Does the actual work of `main()`, returning the number of entries exported on success so the
+/// caller can distinguish that from the various `Status` failure modes.
+fn run() -> Result<usize, Status> {
     unsafe {
         // 1. Get Data from Host
         // Replaces: GetRequest() and GetCredentials()
-        let request_buffer = get_buffer_from_host(GetRequestSize, GetRequestBuffer);
-        
+        let mut request_buffer = get_buffer_from_host(host::GetRequestSize, host::GetRequestBuffer);
+
         let mut creds_total_size: u32 = 0;
-        GetCredentialsSize(&mut creds_total_size);
+        host::GetCredentialsSize(&mut creds_total_size);
         let mut credentials_buffer = Vec::with_capacity(creds_total_size as usize);
-        ReadCredentialsBuffer(credentials_buffer.as_mut_ptr() as *mut c_void, 0, creds_total_size as usize);
+        host::ReadCredentialsBuffer(credentials_buffer.as_mut_ptr() as *mut c_void, 0, creds_total_size as usize);
         credentials_buffer.set_len(creds_total_size as usize);
 
-        // 2. Get App Info (Preserving logic from C, though unused)
+        // 2. Get App Info and decode the caller's identity for allowlist matching below.
         let mut app_info = CallingAppInfo { package_name: [0; 256], origin: [0; 512] };
-        GetCallingAppInfo(&mut app_info);
-
-        // 3. Parse Binary Header from credentials_buffer
-        // Layout: [header_size (4b)][creds_size (4b)][icon_count (4b)][icon_size_1][icon_size_2]...
-        if credentials_buffer.len() < 12 { return 0; } // Safety check
-
-        let header_size = read_i32(&credentials_buffer, 0) as usize;
-        let creds_size = read_i32(&credentials_buffer, 4) as usize;
-        let icon_count = read_i32(&credentials_buffer, 8) as usize;
-
-        // Calculate Icon Offsets
-        // The C code calculates absolute pointers. In Rust, we calculate offsets relative to the buffer.
-        let mut icon_offsets = Vec::new();
-        let mut current_icon_start = header_size + creds_size; // Start of icon data block
-        
-        // Loop through the icon size array which sits at offset 12
-        for i in 0..icon_count {
-            let size_offset = 12 + (i * 4);
-            let this_icon_size = read_i32(&credentials_buffer, size_offset) as usize;
-            
-            icon_offsets.push((current_icon_start, this_icon_size));
-            current_icon_start += this_icon_size;
-        }
+        host::GetCallingAppInfo(&mut app_info);
+        let calling_app = CallingApp::decode(&app_info);
+
+        // 3. Parse Binary Header from credentials_buffer (bounds-checked, zero-copy)
+        let parsed = parser::parse_credentials(&credentials_buffer)?;
+        let icon_offsets = parsed.icon_offsets;
+        let json_range = parsed.json_range;
+
+        // 4. Parse JSON (SIMD-accelerated when the `simd` feature is on, serde_json otherwise)
+        let request_json: Request = json::parse_json(&mut request_buffer)
+            .map_err(|_| Status::InvalidRequest)?;
 
-        // 4. Parse JSON
-        // Request JSON
-        let request_str = match std::str::from_utf8(&request_buffer) {
-            Ok(s) => s,
-            Err(_) => return 0,
-        };
-        let request_json: Request = match serde_json::from_str(request_str) {
-            Ok(j) => j,
-            Err(_) => return 0,
-        };
-
-        // Credentials JSON
-        // Ensure we don't read out of bounds. The JSON starts at `header_size`.
-        if header_size >= credentials_buffer.len() { return 0; }
-        
-        // We need to slice strictly the JSON part. The C code implies the JSON is 
-        // located at `header_size`, but standard cJSON parsing usually stops at null or matching braces.
-        // We will slice from header_size up to the start of icons.
-        let json_end = header_size + creds_size;
-        let creds_json_slice = &credentials_buffer[header_size..json_end];
-        let creds_json_str = match std::str::from_utf8(creds_json_slice) {
-            Ok(s) => s.trim_matches(char::from(0)), // Remove potential null terminators
-            Err(_) => return 0,
-        };
-
-        let creds_json: Credentials = match serde_json::from_str(creds_json_str) {
-            Ok(j) => j,
-            Err(_) => return 0, // Failed to parse credentials JSON
-        };
+        let creds_json: Credentials = json::parse_json(&mut credentials_buffer[json_range])
+            .map_err(|_| Status::InvalidCredentials)?;
 
         // 5. Matching Logic
+        let mut exported_count = 0;
+        let request_transfer = request_json.transfer.unwrap_or_default();
         if let Some(req_types) = request_json.credential_types {
             if let Some(entries) = creds_json.entries {
                 for entry in entries {
-                    let mut matched = false;
-
-                    // Check if supported types match requested types
-                    if let Some(ref supported) = entry.supported_credential_types {
-                        for supp_type in supported {
-                            if req_types.contains(supp_type) {
-                                matched = true;
-                                break;
-                            }
-                        }
+                    let Some(kind) = entry.matching_kind(&req_types) else {
+                        continue;
+                    };
+                    if !calling_app.is_allowed(&entry) {
+                        continue;
                     }
+                    let mode = entry.transfer_mode(request_transfer);
 
-                    if matched {
-                        // Prepare data for export
-                        let id = CString::new(entry.id).unwrap();
-                        let username = CString::new(entry.display_info.user_name).unwrap();
-                        let provider = CString::new("default_provider").unwrap(); // Hardcoded in C
-                        
-                        let account_name_str = entry.display_info.account_name.unwrap_or_default();
-                        // C code passes "account_name" variable to "display_name" param
-                        let display_name = CString::new(account_name_str).unwrap_or_default();
-
-                        // Handle Icon
-                        let mut icon_ptr: *const u8 = std::ptr::null();
-                        let mut icon_len: usize = 0;
-
+                    // By-reference matches skip icon-offset resolution entirely: there's no
+                    // point copying icon bytes into an entry the user may never select.
+                    let mut icon = Icon::none();
+                    if mode == model::TransferMode::ByValue {
                         if let Some(icon_idx) = entry.display_info.icon_id {
-                            if icon_idx < icon_offsets.len() {
-                                let (offset, len) = icon_offsets[icon_idx];
-                                // Get pointer to specific slice in buffer
-                                icon_ptr = credentials_buffer.as_ptr().add(offset);
-                                icon_len = len;
+                            // An out-of-range `icon_id` shouldn't blank out the whole response;
+                            // just export this one entry without its icon, same as if it had none.
+                            if let Some(&(offset, len)) = icon_offsets.get(icon_idx) {
+                                // `offset..offset+len` was already validated against the buffer
+                                // length by `parser::parse_credentials`.
+                                icon.ptr = credentials_buffer[offset..offset + len].as_ptr();
+                                icon.len = len;
                             }
                         }
-
-                        // Call Host Function
-                        AddExportEntry(
-                            id.as_ptr(),
-                            icon_ptr,
-                            icon_len,
-                            username.as_ptr(),
-                            provider.as_ptr(),
-                            display_name.as_ptr()
-                        );
                     }
+
+                    dispatch::dispatch_entry(&entry, kind, icon, mode);
+                    exported_count += 1;
                 }
             }
         }
-    }
 
-    0
-}
\ No newline at end of file
+        Ok(exported_count)
+    }
+}
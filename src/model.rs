@@ -0,0 +1,167 @@
+//! JSON schema for the request/credentials buffers, plus the credential-type model used to pick
+//! which host entry function a matched entry is rendered through.
+
+use serde::Deserialize;
+
+// JSON: Request Schema
+#[derive(Deserialize)]
+pub struct Request {
+    #[serde(rename = "credentialTypes")]
+    pub credential_types: Option<Vec<String>>,
+    pub transfer: Option<TransferMode>,
+}
+
+// JSON: Display Info inside an Entry
+#[derive(Deserialize)]
+pub struct DisplayInfo {
+    pub user_name: String,
+    pub icon_id: Option<usize>,
+    pub account_name: Option<String>,
+    pub provider_name: Option<String>,
+    pub disclaimer: Option<String>,
+    pub warning: Option<String>,
+}
+
+// JSON: Entry Schema
+#[derive(Deserialize)]
+pub struct Entry {
+    pub id: String,
+    pub supported_credential_types: Option<Vec<String>>,
+    pub display_info: DisplayInfo,
+    pub transfer: Option<TransferMode>,
+    /// Caller package names allowed to receive this entry. `None` allows any caller; `["*"]`
+    /// is an explicit wildcard for entries that want to opt in to that without omitting the
+    /// field entirely.
+    pub allowed_packages: Option<Vec<String>>,
+    /// Caller origins allowed to receive this entry. Same `None`/`"*"` semantics as
+    /// `allowed_packages`.
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+// JSON: Credentials Root Schema
+#[derive(Deserialize)]
+pub struct Credentials {
+    pub entries: Option<Vec<Entry>>,
+}
+
+/// Whether a matched entry discloses its full credential data up front, or only enough for the
+/// host to identify it and fetch the rest later. Borrowed from the `ByValue`/`ByReference`
+/// distinction in the lakers EDHOC credentials redesign. Defaults to `ByReference`, since that's
+/// the cheaper and more private choice when a caller doesn't say otherwise.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    ByValue,
+    #[default]
+    ByReference,
+}
+
+/// Which host entry function (and which fields) a matched `Entry` should be rendered through.
+/// Parsed from `Entry::supported_credential_types`; unrecognized types fall through to
+/// `Custom` so the dispatcher still has something sensible to do with them.
+pub enum CredentialKind {
+    Password,
+    Passkey,
+    Export,
+    Custom(String),
+}
+
+impl CredentialKind {
+    pub fn parse(credential_type: &str) -> CredentialKind {
+        match credential_type {
+            "password" => CredentialKind::Password,
+            "passkey" => CredentialKind::Passkey,
+            "export" => CredentialKind::Export,
+            other => CredentialKind::Custom(other.to_string()),
+        }
+    }
+}
+
+impl Entry {
+    /// The first credential kind this entry supports that's also present in `req_types`, if any.
+    pub fn matching_kind(&self, req_types: &[String]) -> Option<CredentialKind> {
+        let supported = self.supported_credential_types.as_ref()?;
+        supported
+            .iter()
+            .find(|supp_type| req_types.contains(supp_type))
+            .map(|supp_type| CredentialKind::parse(supp_type))
+    }
+
+    /// This entry's disclosure mode, falling back to the request-level default when the entry
+    /// doesn't specify its own.
+    pub fn transfer_mode(&self, request_default: TransferMode) -> TransferMode {
+        self.transfer.unwrap_or(request_default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(supported_credential_types: Option<Vec<String>>, transfer: Option<TransferMode>) -> Entry {
+        Entry {
+            id: "entry-1".to_string(),
+            supported_credential_types,
+            display_info: DisplayInfo {
+                user_name: "alice".to_string(),
+                icon_id: None,
+                account_name: None,
+                provider_name: None,
+                disclaimer: None,
+                warning: None,
+            },
+            transfer,
+            allowed_packages: None,
+            allowed_origins: None,
+        }
+    }
+
+    fn types(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn matching_kind_recognizes_known_types() {
+        let e = entry(Some(types(&["password"])), None);
+        assert!(matches!(e.matching_kind(&types(&["password"])), Some(CredentialKind::Password)));
+    }
+
+    #[test]
+    fn matching_kind_falls_back_to_custom_for_unrecognized_types() {
+        let e = entry(Some(types(&["my-custom-type"])), None);
+        match e.matching_kind(&types(&["my-custom-type"])) {
+            Some(CredentialKind::Custom(name)) => assert_eq!(name, "my-custom-type"),
+            _ => panic!("expected Custom(\"my-custom-type\")"),
+        }
+    }
+
+    #[test]
+    fn matching_kind_is_none_when_nothing_overlaps() {
+        let e = entry(Some(types(&["password"])), None);
+        assert!(e.matching_kind(&types(&["passkey"])).is_none());
+    }
+
+    #[test]
+    fn matching_kind_is_none_when_entry_supports_nothing() {
+        let e = entry(None, None);
+        assert!(e.matching_kind(&types(&["password"])).is_none());
+    }
+
+    #[test]
+    fn transfer_mode_prefers_the_entry_override() {
+        let e = entry(None, Some(TransferMode::ByValue));
+        assert!(e.transfer_mode(TransferMode::ByReference) == TransferMode::ByValue);
+    }
+
+    #[test]
+    fn transfer_mode_falls_back_to_the_request_default() {
+        let e = entry(None, None);
+        assert!(e.transfer_mode(TransferMode::ByValue) == TransferMode::ByValue);
+    }
+
+    #[test]
+    fn transfer_mode_falls_back_to_byreference_when_nothing_is_specified() {
+        let e = entry(None, None);
+        assert!(e.transfer_mode(TransferMode::default()) == TransferMode::ByReference);
+    }
+}
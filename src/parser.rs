@@ -0,0 +1,147 @@
+//! Bounds-checked parsing of the binary credentials header.
+//!
+//! Layout: `[header_size u32][creds_size u32][icon_count u32][icon_size[0] u32]...[icon_size[n-1] u32]`
+//! followed by the JSON credentials blob (`header_size..header_size+creds_size`) and then the
+//! concatenated icon data blocks. `Ref::from_prefix` lets us reinterpret the bytes in place
+//! instead of copying them, while every offset below is computed with checked arithmetic so a
+//! truncated or hostile buffer can never alias past the allocation.
+
+use zerocopy::byteorder::{LittleEndian, U32};
+use zerocopy::{FromBytes, Immutable, KnownLayout, Ref};
+
+#[derive(FromBytes, KnownLayout, Immutable)]
+#[repr(C)]
+pub struct CredHeader {
+    pub header_size: u32,
+    pub creds_size: u32,
+    pub icon_count: u32,
+}
+
+/// Everything that can go wrong while validating the credentials buffer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CredError {
+    /// Buffer is shorter than a `CredHeader`.
+    HeaderTooShort,
+    /// The icon size table runs past the end of the buffer.
+    IconTableOverrun,
+    /// `header_size + creds_size` overflows or exceeds the buffer length.
+    JsonRegionOverrun,
+    /// The cumulative icon sizes overflow or exceed the remaining bytes.
+    IconDataOverrun,
+}
+
+/// A validated view over the credentials buffer: the byte range of the JSON blob and the
+/// resolved `(offset, len)` of every icon, all guaranteed to lie within the buffer that produced
+/// them. Holding a plain range rather than a borrowed slice lets callers still mutably reslice
+/// the buffer afterwards (e.g. to parse the JSON blob in place).
+#[derive(Debug)]
+pub struct ParsedCredentials {
+    pub json_range: std::ops::Range<usize>,
+    pub icon_offsets: Vec<(usize, usize)>,
+}
+
+/// Validates and reinterprets `buffer` as a [`CredHeader`] plus icon table, JSON blob, and icon
+/// data region, without ever trusting a length or offset it didn't check first.
+pub fn parse_credentials(buffer: &[u8]) -> Result<ParsedCredentials, CredError> {
+    let (header, rest) =
+        Ref::<_, CredHeader>::from_prefix(buffer).map_err(|_| CredError::HeaderTooShort)?;
+
+    let icon_count = header.icon_count as usize;
+    let (icon_sizes, _) = Ref::<_, [U32<LittleEndian>]>::from_prefix_with_elems(rest, icon_count)
+        .map_err(|_| CredError::IconTableOverrun)?;
+
+    let header_size = header.header_size as usize;
+    let creds_size = header.creds_size as usize;
+    let json_end = header_size
+        .checked_add(creds_size)
+        .ok_or(CredError::JsonRegionOverrun)?;
+    if json_end > buffer.len() {
+        return Err(CredError::JsonRegionOverrun);
+    }
+
+    let mut icon_offsets = Vec::with_capacity(icon_count);
+    let mut cursor = json_end;
+    for size in icon_sizes.iter() {
+        let size = size.get() as usize;
+        let end = cursor.checked_add(size).ok_or(CredError::IconDataOverrun)?;
+        if end > buffer.len() {
+            return Err(CredError::IconDataOverrun);
+        }
+        icon_offsets.push((cursor, size));
+        cursor = end;
+    }
+
+    Ok(ParsedCredentials {
+        json_range: header_size..json_end,
+        icon_offsets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds `[header_size][creds_size][icon_count][icon_size...]` (little-endian, per the host
+    /// ABI) followed by `json` and then one icon data block per `icon_sizes` entry, that many bytes.
+    fn build_buffer(header_size: u32, creds_size: u32, icon_sizes: &[u32], json: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&header_size.to_le_bytes());
+        buf.extend_from_slice(&creds_size.to_le_bytes());
+        buf.extend_from_slice(&(icon_sizes.len() as u32).to_le_bytes());
+        for size in icon_sizes {
+            buf.extend_from_slice(&size.to_le_bytes());
+        }
+        buf.extend_from_slice(json);
+        for &size in icon_sizes {
+            buf.extend(std::iter::repeat_n(0xAA, size as usize));
+        }
+        buf
+    }
+
+    #[test]
+    fn valid_buffer_resolves_json_range_and_icon_offsets() {
+        // 12-byte fixed header + a 2-entry icon size table (8 bytes) = JSON starts at 20.
+        let buf = build_buffer(20, 4, &[2, 3], b"{}__");
+        let parsed = parse_credentials(&buf).unwrap();
+        assert_eq!(parsed.json_range, 20..24);
+        assert_eq!(parsed.icon_offsets, vec![(24, 2), (26, 3)]);
+    }
+
+    #[test]
+    fn buffer_shorter_than_header_is_rejected() {
+        let buf = vec![0u8; 8]; // a CredHeader is 12 bytes
+        assert_eq!(parse_credentials(&buf).unwrap_err(), CredError::HeaderTooShort);
+    }
+
+    #[test]
+    fn icon_table_overrunning_buffer_is_rejected() {
+        // Header claims 5 icon sizes but the buffer doesn't have room for them.
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&5u32.to_le_bytes());
+        buf.extend_from_slice(&1u32.to_le_bytes()); // only one icon size present
+        assert_eq!(parse_credentials(&buf).unwrap_err(), CredError::IconTableOverrun);
+    }
+
+    #[test]
+    fn header_plus_creds_size_overflow_is_rejected() {
+        let buf = build_buffer(u32::MAX, 1, &[], b"");
+        assert_eq!(parse_credentials(&buf).unwrap_err(), CredError::JsonRegionOverrun);
+    }
+
+    #[test]
+    fn header_plus_creds_size_past_buffer_end_is_rejected() {
+        let buf = build_buffer(12, 100, &[], b"{}");
+        assert_eq!(parse_credentials(&buf).unwrap_err(), CredError::JsonRegionOverrun);
+    }
+
+    #[test]
+    fn cumulative_icon_size_past_buffer_end_is_rejected() {
+        // 12-byte fixed header + a 1-entry icon table (4 bytes) = JSON starts at 16; the single
+        // icon then claims 100 bytes that were never appended to the buffer.
+        let mut buf = build_buffer(16, 2, &[100], b"{}");
+        buf.truncate(18); // drop the icon data entirely, keeping header + table + json
+        assert_eq!(parse_credentials(&buf).unwrap_err(), CredError::IconDataOverrun);
+    }
+}
@@ -0,0 +1,31 @@
+//! Distinguishes the different ways `run()` can fail (or succeed), so the host can tell a clean
+//! "no matches" apart from a malformed buffer, invalid UTF-8, or a JSON parse error instead of
+//! getting the same `0` for all of them.
+
+use crate::parser::CredError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Status {
+    Ok = 0,
+    InvalidRequest = 1,
+    InvalidCredentials = 2,
+    BufferTooSmall = 3,
+}
+
+impl Status {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+}
+
+impl From<CredError> for Status {
+    fn from(err: CredError) -> Status {
+        match err {
+            CredError::HeaderTooShort => Status::BufferTooSmall,
+            CredError::IconTableOverrun
+            | CredError::JsonRegionOverrun
+            | CredError::IconDataOverrun => Status::InvalidCredentials,
+        }
+    }
+}